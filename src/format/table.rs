@@ -3,7 +3,8 @@ use crate::format::RenderView;
 use crate::prelude::*;
 use derive_new::new;
 use nu_errors::ShellError;
-use nu_protocol::{UntaggedValue, Value};
+use nu_protocol::{Primitive, UntaggedValue, Value};
+use std::io::Write;
 use textwrap::fill;
 
 use prettytable::format::{FormatBuilder, LinePosition, LineSeparator};
@@ -15,12 +16,57 @@ pub struct TableView {
     headers: Vec<String>,
 
     // List of rows of cells, each containing value and prettytable style-string:
-    entries: Vec<Vec<(String, &'static str)>>,
+    entries: Vec<Vec<(String, String)>>,
+
+    // Set when the normal grid layout would have truncated columns, so the
+    // expanded record view should be used even without an explicit config:
+    would_truncate: bool,
+
+    // Raw values for a list of bare scalars (the synthetic `<value>` column
+    // only); non-empty only for that shape, and rendered as a packed grid
+    // instead of the usual one-row-per-value table:
+    grid_values: Vec<String>,
 }
 
 enum TableMode {
     Light,
     Normal,
+    Expanded,
+}
+
+// Controls how a cell that's wider than its column is brought down to size.
+#[derive(Clone, Copy)]
+enum TableTrim {
+    Wrap,
+    Truncate,
+}
+
+// Which way a packed grid fills its cells: left-to-right row by row, or
+// top-to-bottom column by column (the way `ls` lays out a plain directory
+// listing).
+#[derive(Clone, Copy)]
+enum GridDirection {
+    Across,
+    Down,
+}
+
+// A column's alignment, derived from the type of the values it holds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    // The prettytable style_spec character for this alignment.
+    fn style_char(self) -> char {
+        match self {
+            Alignment::Left => 'l',
+            Alignment::Center => 'c',
+            Alignment::Right => 'r',
+        }
+    }
 }
 
 impl TableView {
@@ -45,6 +91,132 @@ impl TableView {
         ret
     }
 
+    // Cuts `text` down to `max_width` characters flat, appending a single
+    // ellipsis character when anything was actually removed. Backs up to the
+    // previous word boundary rather than splitting the last visible word.
+    fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
+        if max_width == 0 || text.chars().count() <= max_width {
+            return text.to_string();
+        }
+
+        let keep = max_width - 1;
+        let mut truncated: String = text.chars().take(keep).collect();
+
+        if text.chars().nth(keep) != Some(' ') {
+            if let Some(last_space) = truncated.rfind(' ') {
+                truncated.truncate(last_space);
+            }
+        }
+
+        truncated = truncated.trim_end().to_string();
+        truncated.push('…');
+        truncated
+    }
+
+    // The alignment a single descriptor of a single value should contribute
+    // to its column, or `None` for an empty/missing cell (which shouldn't
+    // count toward the column's alignment either way).
+    fn cell_alignment(value: &Value, desc: &str) -> Option<Alignment> {
+        if desc == "<value>" {
+            match value {
+                Value {
+                    value: UntaggedValue::Row(..),
+                    ..
+                } => None,
+                _ => TableView::alignment_for(&value.value),
+            }
+        } else {
+            match value {
+                Value {
+                    value: UntaggedValue::Row(..),
+                    ..
+                } => {
+                    let data = value.get_data(desc);
+                    TableView::alignment_for(data.borrow())
+                }
+                _ => None,
+            }
+        }
+    }
+
+    fn alignment_for(value: &UntaggedValue) -> Option<Alignment> {
+        match value {
+            UntaggedValue::Primitive(Primitive::Nothing) => None,
+            UntaggedValue::Primitive(Primitive::Int(_)) => Some(Alignment::Right),
+            UntaggedValue::Primitive(Primitive::Decimal(_)) => Some(Alignment::Right),
+            UntaggedValue::Primitive(Primitive::Filesize(_)) => Some(Alignment::Right),
+            UntaggedValue::Primitive(Primitive::Duration(_)) => Some(Alignment::Right),
+            UntaggedValue::Primitive(Primitive::Boolean(_)) => Some(Alignment::Center),
+            _ => Some(Alignment::Left),
+        }
+    }
+
+    // Decides each column's alignment from the type of the non-empty cells
+    // it holds across every row, so a single stray cell of a different type
+    // doesn't flip the whole column (e.g. a mix of ints and strings stays
+    // left-aligned, same as an all-string column).
+    fn column_alignments(values: &[Value], headers: &[String]) -> Vec<Alignment> {
+        let mut votes: Vec<Vec<Alignment>> = vec![Vec::new(); headers.len()];
+
+        for value in values {
+            for (col, desc) in headers.iter().enumerate() {
+                if let Some(alignment) = TableView::cell_alignment(value, desc) {
+                    votes[col].push(alignment);
+                }
+            }
+        }
+
+        votes
+            .into_iter()
+            .map(|column_votes| {
+                if !column_votes.is_empty() && column_votes.iter().all(|a| *a == Alignment::Right) {
+                    Alignment::Right
+                } else if !column_votes.is_empty()
+                    && column_votes.iter().all(|a| *a == Alignment::Center)
+                {
+                    Alignment::Center
+                } else {
+                    Alignment::Left
+                }
+            })
+            .collect()
+    }
+
+    // Formats a single descriptor of a single value into a table cell,
+    // looking up `desc` on rows and falling back to the value itself for
+    // the synthetic `<value>` column.
+    fn format_cell(value: &Value, desc: &str) -> (String, &'static str) {
+        if desc == "<value>" {
+            match value {
+                Value {
+                    value: UntaggedValue::Row(..),
+                    ..
+                } => (
+                    format_leaf(&UntaggedValue::nothing()).plain_string(100_000),
+                    style_leaf(&UntaggedValue::nothing()),
+                ),
+                _ => (format_leaf(value).plain_string(100_000), style_leaf(value)),
+            }
+        } else {
+            match value {
+                Value {
+                    value: UntaggedValue::Row(..),
+                    ..
+                } => {
+                    let data = value.get_data(desc);
+                    (
+                        format_leaf(data.borrow()).plain_string(100_000),
+                        style_leaf(data.borrow()),
+                    )
+                }
+                _ => (
+                    format_leaf(&UntaggedValue::nothing()).plain_string(100_000),
+                    style_leaf(&UntaggedValue::nothing()),
+                ),
+            }
+        }
+    }
+
     pub fn from_list(values: &[Value], starting_idx: usize) -> Option<TableView> {
         if values.is_empty() {
             return None;
@@ -56,52 +228,56 @@ impl TableView {
             headers.push("<value>".to_string());
         }
 
-        let mut entries = vec![];
+        // A list of bare scalars (file names, numbers, ...) merges down to
+        // just the synthetic `<value>` column; pack those into a grid
+        // instead of wasting a full row per entry.
+        let grid_values: Vec<String> = if headers.len() == 1 && headers[0] == "<value>" {
+            values
+                .iter()
+                .map(|v| format_leaf(v).plain_string(100_000))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        // Decide each column's alignment once, from the type of the values it
+        // holds: right-align a column only if every non-empty cell in it is
+        // numeric, center it only if every non-empty cell is a boolean, and
+        // otherwise leave it left-aligned.
+        let column_alignments = TableView::column_alignments(values, &headers);
+
+        let mut entries: Vec<Vec<(String, String)>> = vec![];
 
         for (idx, value) in values.iter().enumerate() {
-            let mut row: Vec<(String, &'static str)> = headers
+            let mut row: Vec<(String, String)> = headers
                 .iter()
                 .map(|d| {
-                    if d == "<value>" {
-                        match value {
-                            Value {
-                                value: UntaggedValue::Row(..),
-                                ..
-                            } => (
-                                format_leaf(&UntaggedValue::nothing()).plain_string(100_000),
-                                style_leaf(&UntaggedValue::nothing()),
-                            ),
-                            _ => (format_leaf(value).plain_string(100_000), style_leaf(value)),
-                        }
-                    } else {
-                        match value {
-                            Value {
-                                value: UntaggedValue::Row(..),
-                                ..
-                            } => {
-                                let data = value.get_data(d);
-                                (
-                                    format_leaf(data.borrow()).plain_string(100_000),
-                                    style_leaf(data.borrow()),
-                                )
-                            }
-                            _ => (
-                                format_leaf(&UntaggedValue::nothing()).plain_string(100_000),
-                                style_leaf(&UntaggedValue::nothing()),
-                            ),
-                        }
-                    }
+                    let (text, style) = TableView::format_cell(value, d);
+                    (text, style.to_owned())
                 })
                 .collect();
 
             if values.len() > 1 {
                 // Indices are black, bold, right-aligned:
-                row.insert(0, ((starting_idx + idx).to_string(), "Fdbr"));
+                row.insert(0, ((starting_idx + idx).to_string(), "Fdbr".to_owned()));
             }
 
             entries.push(row);
         }
 
+        let index_offset = if values.len() > 1 { 1 } else { 0 };
+
+        for (col, alignment) in column_alignments.iter().enumerate() {
+            if *alignment == Alignment::Left {
+                continue;
+            }
+
+            for entry in &mut entries {
+                let cell = &mut entry[col + index_offset];
+                cell.1.push(alignment.style_char());
+            }
+        }
+
         let mut max_per_column = vec![];
 
         if values.len() > 1 {
@@ -128,102 +304,324 @@ impl TableView {
         // Make sure we have enough space for the columns we have
         let max_num_of_columns = termwidth / 10;
 
-        // If we have too many columns, truncate the table
-        if max_num_of_columns < headers.len() {
-            headers.truncate(max_num_of_columns);
+        // Too many columns for the normal grid layout: `render_view` falls
+        // back to `render_expanded` in this case, which prints every field
+        // in full, so `headers`/`entries` are left untouched here rather
+        // than being chopped down to a narrow "..." grid that would never
+        // get used anyway.
+        let would_truncate = max_num_of_columns < headers.len();
 
-            for entry in &mut entries {
-                entry.truncate(max_num_of_columns);
-            }
+        // Total space left for column content once separators are accounted for
+        // (3 per internal gap, plus the outer padding column on each side).
+        let num_separators = headers.len().saturating_sub(1);
+        let content_width = termwidth.saturating_sub(3 * num_separators + 2);
+
+        // Never shrink a column below its header width (or a small floor, for the
+        // rare case of an empty header).
+        let min_width: Vec<usize> = headers
+            .iter()
+            .map(|h| std::cmp::max(h.chars().count(), 3))
+            .collect();
+
+        let column_widths =
+            TableView::proportional_widths(&max_per_column, &min_width, content_width);
+
+        let trim_mode = crate::data::config::config(Tag::unknown())
+            .ok()
+            .and_then(|cfg| cfg.get("table_trim").map(|s| s.as_string().unwrap()))
+            .map(|s| match s.as_ref() {
+                "truncate" => TableTrim::Truncate,
+                _ => TableTrim::Wrap,
+            })
+            .unwrap_or(TableTrim::Wrap);
+
+        // The expanded view wraps each value to its own width, so cells keep
+        // their full text instead of being cut to the grid's column widths.
+        if would_truncate {
+            return Some(TableView {
+                headers,
+                entries,
+                would_truncate,
+                grid_values,
+            });
+        }
+
+        // Bring cells down to their final, possibly column-specific, width
+        for head in 0..headers.len() {
+            let width = column_widths[head];
+            let shrink = |s: &str| match trim_mode {
+                TableTrim::Wrap => fill(s, width),
+                TableTrim::Truncate => TableView::truncate_with_ellipsis(s, width),
+            };
+
+            headers[head] = shrink(&headers[head]);
 
-            headers.push("...".to_owned());
             for entry in &mut entries {
-                entry.push(("...".to_owned(), "c")); // ellipsis is centred
+                entry[head].0 = shrink(&entry[head].0);
             }
         }
 
-        // Measure how big our columns need to be (accounting for separators also)
-        let max_naive_column_width = (termwidth - 3 * (headers.len() - 1)) / headers.len();
+        Some(TableView {
+            headers,
+            entries,
+            would_truncate,
+            grid_values,
+        })
+    }
+
+    // Fits `max_per_column` (each column's desired width) into `content_width`,
+    // never shrinking a column below its `min_width`. If the desired widths
+    // overflow the available space, columns are shrunk toward their floor in
+    // proportion to how far over the fair share they are, fixing (and
+    // excluding from further shrinking) any column that hits its floor and
+    // re-dividing the remaining budget among the rest. If there's slack
+    // instead, it's handed out proportionally to each column's desired width.
+    fn proportional_widths(
+        max_per_column: &[usize],
+        min_width: &[usize],
+        content_width: usize,
+    ) -> Vec<usize> {
+        let mut column_widths = max_per_column.to_vec();
+        let desired_total: usize = max_per_column.iter().sum();
+
+        if desired_total > content_width {
+            let mut fixed = vec![false; column_widths.len()];
 
-        // Measure how much space we have once we subtract off the columns who are small enough
-        let mut num_overages = 0;
-        let mut underage_sum = 0;
-        let mut overage_separator_sum = 0;
-        let iter = max_per_column.iter().enumerate().take(headers.len());
-        for (i, &column_max) in iter {
-            if column_max > max_naive_column_width {
-                num_overages += 1;
-                if i != (headers.len() - 1) {
-                    overage_separator_sum += 3;
+            loop {
+                let flexible: Vec<usize> =
+                    (0..column_widths.len()).filter(|&i| !fixed[i]).collect();
+                if flexible.is_empty() {
+                    break;
                 }
-                if i == 0 {
-                    overage_separator_sum += 1;
+
+                let fixed_sum: usize = (0..column_widths.len())
+                    .filter(|&i| fixed[i])
+                    .map(|i| column_widths[i])
+                    .sum();
+                let remaining_budget = content_width.saturating_sub(fixed_sum);
+                let flexible_desired: usize = flexible.iter().map(|&i| max_per_column[i]).sum();
+
+                if flexible_desired == 0 {
+                    break;
                 }
-            } else {
-                underage_sum += column_max;
-                // if column isn't last, add 3 for its separator
-                if i != (headers.len() - 1) {
-                    underage_sum += 3;
+
+                // Each column gives up a part of the overflow in proportion to
+                // how far its desired width exceeds an even split of the budget.
+                let fair_share = remaining_budget / flexible.len();
+                let overflow = flexible_desired.saturating_sub(remaining_budget);
+                let excess = |i: usize| max_per_column[i].saturating_sub(fair_share);
+                let total_excess = flexible.iter().map(|&i| excess(i)).sum::<usize>().max(1);
+
+                let mut newly_fixed = false;
+                for &i in &flexible {
+                    let shrink = (overflow * excess(i) + total_excess - 1) / total_excess;
+                    let width = max_per_column[i] - shrink;
+
+                    if width <= min_width[i] {
+                        column_widths[i] = min_width[i];
+                        fixed[i] = true;
+                        newly_fixed = true;
+                    } else {
+                        column_widths[i] = width;
+                    }
                 }
-                if i == 0 {
-                    underage_sum += 1;
+
+                if !newly_fixed {
+                    break;
                 }
             }
+        } else if desired_total < content_width && desired_total > 0 {
+            let slack = content_width - desired_total;
+            for (i, width) in column_widths.iter_mut().enumerate() {
+                *width += (slack * max_per_column[i]) / desired_total;
+            }
         }
 
-        // This gives us the max column width
-        let max_column_width = if num_overages > 0 {
-            (termwidth - 1 - underage_sum - overage_separator_sum) / num_overages
-        } else {
-            99999
-        };
+        column_widths
+    }
 
-        // This width isn't quite right, as we're rounding off some of our space
-        num_overages = 0;
-        overage_separator_sum = 0;
-        let iter = max_per_column.iter().enumerate().take(headers.len());
-        for (i, &column_max) in iter {
-            if column_max > max_naive_column_width {
-                if column_max <= max_column_width {
-                    underage_sum += column_max;
-                    // if column isn't last, add 3 for its separator
-                    if i != (headers.len() - 1) {
-                        underage_sum += 3;
-                    }
-                    if i == 0 {
-                        underage_sum += 1;
-                    }
-                } else {
-                    // Column is still too large, so let's count it
-                    num_overages += 1;
-                    if i != (headers.len() - 1) {
-                        overage_separator_sum += 3;
-                    }
-                    if i == 0 {
-                        overage_separator_sum += 1;
-                    }
+    // Searches for the smallest number of lines that packs `widths` into a
+    // grid fitting within `termwidth`, trying `num_lines = 1, 2, ...` in
+    // turn. Returns `(num_lines, num_columns, col_widths)`, falling back to
+    // one value per line if nothing fits.
+    fn fit_grid(
+        widths: &[usize],
+        termwidth: usize,
+        separator_width: usize,
+        direction: GridDirection,
+    ) -> (usize, usize, Vec<usize>) {
+        let num_values = widths.len();
+
+        for num_lines in 1..=num_values {
+            let num_columns = (num_values + num_lines - 1) / num_lines;
+
+            // Early abort this candidate: even with zero-width cells, the
+            // separators alone don't fit. A larger `num_lines` shrinks
+            // `num_columns` (and so the separator count), so it may still
+            // fit -- keep searching rather than giving up entirely.
+            if separator_width * num_columns.saturating_sub(1) > termwidth {
+                continue;
+            }
+
+            let mut col_widths = vec![0; num_columns];
+            for (i, &width) in widths.iter().enumerate() {
+                let col = match direction {
+                    GridDirection::Across => i % num_columns,
+                    GridDirection::Down => i / num_lines,
+                };
+                if width > col_widths[col] {
+                    col_widths[col] = width;
+                }
+            }
+
+            let total_width: usize =
+                col_widths.iter().sum::<usize>() + separator_width * num_columns.saturating_sub(1);
+
+            if total_width <= termwidth {
+                return (num_lines, num_columns, col_widths);
+            }
+        }
+
+        let widest = widths.iter().copied().max().unwrap_or(0);
+        (num_values, 1, vec![widest])
+    }
+
+    // Packs `self.grid_values` into as many side-by-side columns as fit the
+    // terminal width, trying the smallest number of lines first.
+    fn render_grid(&self, host: &mut dyn Host) -> Result<(), ShellError> {
+        TableView::render_grid_values(&self.grid_values, host)
+    }
+
+    // Packs `values` into as many side-by-side columns as fit the terminal
+    // width, trying the smallest number of lines first. Pulled out of
+    // `render_grid` so `StreamingTableView` can reuse it for a streamed bare
+    // scalar list, which (like the batch case) needs every value collected
+    // up front to pick a layout.
+    fn render_grid_values(values: &[String], host: &mut dyn Host) -> Result<(), ShellError> {
+        let termwidth = std::cmp::max(textwrap::termwidth(), 20);
+        let separator_width = 2;
+
+        let direction = crate::data::config::config(Tag::unknown())
+            .ok()
+            .and_then(|cfg| {
+                cfg.get("table_grid_direction")
+                    .map(|s| s.as_string().unwrap())
+            })
+            .map(|s| match s.as_ref() {
+                "across" => GridDirection::Across,
+                _ => GridDirection::Down,
+            })
+            .unwrap_or(GridDirection::Down);
+
+        let num_values = values.len();
+        let widths: Vec<usize> = values.iter().map(|v| v.chars().count()).collect();
+
+        let (num_lines, num_columns, col_widths) =
+            TableView::fit_grid(&widths, termwidth, separator_width, direction);
+
+        let out = host.out_terminal();
+
+        for line in 0..num_lines {
+            let mut rendered = String::new();
+
+            for col in 0..num_columns {
+                let idx = match direction {
+                    GridDirection::Across => line * num_columns + col,
+                    GridDirection::Down => col * num_lines + line,
+                };
+
+                if idx >= num_values {
+                    continue;
                 }
+
+                if col > 0 {
+                    rendered.push_str(&" ".repeat(separator_width));
+                }
+
+                rendered.push_str(&format!("{:<width$}", values[idx], width = col_widths[col]));
             }
+
+            writeln!(out, "{}", rendered.trim_end())
+                .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    // Prints one record per block in a vertical key/value layout, e.g.:
+    //
+    //   -[ RECORD 0 ]-+----------------
+    //   name │ andres
+    //    age │ 23
+    fn render_expanded(&self, host: &mut dyn Host) -> Result<(), ShellError> {
+        let header_width = self
+            .headers
+            .iter()
+            .map(|h| h.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (idx, entry) in self.entries.iter().enumerate() {
+            TableView::render_expanded_record(&self.headers, entry, idx, header_width, host)?;
         }
-        // This should give us the final max column width
-        let max_column_width = if num_overages > 0 {
-            (termwidth - 1 - underage_sum - overage_separator_sum) / num_overages
+
+        Ok(())
+    }
+
+    // Prints a single record in the vertical key/value layout `render_expanded`
+    // uses, given the header width for the whole set of records (so every
+    // record's "header │ value" column lines up the same way). Pulled out so
+    // `StreamingTableView` can print each record as it streams in, since
+    // unlike the grid case this doesn't need the other records to do it.
+    fn render_expanded_record(
+        headers: &[String],
+        entry: &[(String, String)],
+        idx: usize,
+        header_width: usize,
+        host: &mut dyn Host,
+    ) -> Result<(), ShellError> {
+        let termwidth = std::cmp::max(textwrap::termwidth(), 20);
+
+        let value_width = if termwidth > header_width + 3 {
+            termwidth - header_width - 3
         } else {
-            99999
+            10
         };
 
-        // Wrap cells as needed
-        for head in 0..headers.len() {
-            if max_per_column[head] > max_naive_column_width {
-                headers[head] = fill(&headers[head], max_column_width);
+        let out = host.out_terminal();
 
-                for entry in &mut entries {
-                    entry[head].0 = fill(&entry[head].0, max_column_width);
-                }
+        let record_header = format!("-[ RECORD {} ]-", idx);
+        let dashes_to_separator = (header_width + 1).saturating_sub(record_header.chars().count());
+
+        let mut separator_line = record_header;
+        separator_line.push_str(&"-".repeat(dashes_to_separator));
+        separator_line.push('+');
+        separator_line
+            .push_str(&"-".repeat(termwidth.saturating_sub(separator_line.chars().count())));
+
+        writeln!(out, "{}", separator_line)
+            .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+
+        for (header, (value, _style)) in headers.iter().zip(entry.iter()) {
+            let wrapped = fill(value, value_width);
+            let mut lines = wrapped.lines();
+
+            writeln!(
+                out,
+                "{:>width$} │ {}",
+                header,
+                lines.next().unwrap_or(""),
+                width = header_width
+            )
+            .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+
+            for line in lines {
+                writeln!(out, "{:>width$} │ {}", "", line, width = header_width)
+                    .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
             }
         }
 
-        Some(TableView { headers, entries })
+        Ok(())
     }
 }
 
@@ -233,16 +631,27 @@ impl RenderView for TableView {
             return Ok(());
         }
 
+        if !self.grid_values.is_empty() {
+            return self.render_grid(host);
+        }
+
         let mut table = Table::new();
 
         let table_mode = crate::data::config::config(Tag::unknown())?
             .get("table_mode")
             .map(|s| match s.as_string().unwrap().as_ref() {
                 "light" => TableMode::Light,
+                "expanded" => TableMode::Expanded,
                 _ => TableMode::Normal,
             })
             .unwrap_or(TableMode::Normal);
 
+        // Wide tables are always shown expanded, even without an explicit
+        // config, so records with many fields aren't silently truncated.
+        if self.would_truncate || matches!(table_mode, TableMode::Expanded) {
+            return self.render_expanded(host);
+        }
+
         match table_mode {
             TableMode::Light => {
                 table.set_format(
@@ -290,3 +699,495 @@ impl RenderView for TableView {
         Ok(())
     }
 }
+
+// Which of the shapes `TableView::from_list` can produce a streamed table has
+// settled into, decided from the first window of rows the same way
+// `from_list`/`render_view` decide it for a batch. `Buffering` is the initial
+// state before that decision is made.
+#[derive(Clone, Copy, PartialEq)]
+enum StreamMode {
+    Buffering,
+    Tabular,
+    Expanded,
+    Grid,
+}
+
+/// Renders a table row by row as values arrive, rather than buffering the
+/// whole input like `TableView::from_list` does. Column widths are measured
+/// from a bounded window of the first rows, locked in, and then reused to
+/// format and flush each later row immediately, so a slow or huge pipeline
+/// shows output progressively with bounded memory use. Unlike `TableView`,
+/// this prints its own border directly rather than going through
+/// `prettytable`, since the whole point is a single table that keeps
+/// growing across many separate calls instead of being built all at once.
+///
+/// Once the measuring window decides the shape, from the same checks
+/// `from_list` uses, later rows are streamed through that same shape:
+/// `Tabular` prints each row against locked-in widths, `Expanded` prints
+/// each record as its own vertical block (needing only the header list, not
+/// the other records), and `Grid` -- a bare scalar list, e.g. `ls | get
+/// name` -- buffers every value until `finish`, since packing a grid needs
+/// the full set to pick a layout, same as the batch case.
+pub struct StreamingTableView {
+    mode: StreamMode,
+    headers: Vec<String>,
+    alignments: Vec<Alignment>,
+    widths: Vec<usize>,
+    header_width: usize,
+    has_index: bool,
+    light_mode: bool,
+    trim_mode: TableTrim,
+    grid_values: Vec<String>,
+    window: Vec<Value>,
+    window_size: usize,
+    starting_idx: usize,
+    next_idx: usize,
+}
+
+impl StreamingTableView {
+    // Number of leading rows buffered to measure column widths before they're locked in.
+    const WINDOW_SIZE: usize = 100;
+
+    pub fn new(starting_idx: usize) -> StreamingTableView {
+        StreamingTableView {
+            mode: StreamMode::Buffering,
+            headers: vec![],
+            alignments: vec![],
+            widths: vec![],
+            header_width: 0,
+            has_index: false,
+            light_mode: false,
+            trim_mode: TableTrim::Wrap,
+            grid_values: vec![],
+            window: vec![],
+            window_size: StreamingTableView::WINDOW_SIZE,
+            starting_idx,
+            next_idx: starting_idx,
+        }
+    }
+
+    /// Feed in the next value of the stream. Until the measuring window is
+    /// full this only buffers; once a shape is locked in, each push is
+    /// rendered (or, for a `Grid`, buffered) immediately.
+    pub fn push(&mut self, value: Value, host: &mut dyn Host) -> Result<(), ShellError> {
+        match self.mode {
+            StreamMode::Buffering => {
+                self.window.push(value);
+
+                if self.window.len() >= self.window_size {
+                    self.flush_window(host)?;
+                }
+
+                Ok(())
+            }
+            StreamMode::Grid => {
+                self.grid_values
+                    .push(format_leaf(&value).plain_string(100_000));
+                Ok(())
+            }
+            StreamMode::Expanded => self.print_expanded_row(&value, host),
+            StreamMode::Tabular => self.print_row(&value, host),
+        }
+    }
+
+    /// Flushes any still-buffered rows, renders a buffered grid, and prints
+    /// the closing separator for a tabular table.
+    pub fn finish(&mut self, host: &mut dyn Host) -> Result<(), ShellError> {
+        if self.mode == StreamMode::Buffering && !self.window.is_empty() {
+            self.flush_window(host)?;
+        }
+
+        match self.mode {
+            StreamMode::Grid => TableView::render_grid_values(&self.grid_values, host),
+            // Light mode never drew a boxed border in the first place (it
+            // only has the title separator between header and body), so
+            // there's nothing to close.
+            StreamMode::Tabular if !self.light_mode => {
+                writeln!(host.out_terminal(), "{}", self.border_line('━', '┷'))
+                    .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn flush_window(&mut self, host: &mut dyn Host) -> Result<(), ShellError> {
+        let window = std::mem::take(&mut self.window);
+        if window.is_empty() {
+            return Ok(());
+        }
+
+        let table_mode = crate::data::config::config(Tag::unknown())
+            .ok()
+            .and_then(|cfg| cfg.get("table_mode").map(|s| s.as_string().unwrap()))
+            .map(|s| match s.as_ref() {
+                "light" => TableMode::Light,
+                "expanded" => TableMode::Expanded,
+                _ => TableMode::Normal,
+            })
+            .unwrap_or(TableMode::Normal);
+
+        self.light_mode = matches!(table_mode, TableMode::Light);
+
+        self.trim_mode = crate::data::config::config(Tag::unknown())
+            .ok()
+            .and_then(|cfg| cfg.get("table_trim").map(|s| s.as_string().unwrap()))
+            .map(|s| match s.as_ref() {
+                "truncate" => TableTrim::Truncate,
+                _ => TableTrim::Wrap,
+            })
+            .unwrap_or(TableTrim::Wrap);
+
+        let mut headers = TableView::merge_descriptors(&window);
+        if headers.is_empty() {
+            headers.push("<value>".to_string());
+        }
+
+        // A bare scalar list merges down to just the synthetic `<value>`
+        // column, same as `TableView::from_list`; pack it into a grid
+        // instead of one value per line, same as the batch case.
+        if headers.len() == 1 && headers[0] == "<value>" {
+            self.mode = StreamMode::Grid;
+            self.grid_values
+                .extend(window.iter().map(|v| format_leaf(v).plain_string(100_000)));
+            return Ok(());
+        }
+
+        self.has_index = window.len() > 1;
+
+        let mut alignments = TableView::column_alignments(&window, &headers);
+
+        // Widths are measured from the raw, unwrapped/untruncated cell text
+        // for every row in the window, not from an already-formatted
+        // `TableView` (whose cells may have been wrapped to fit that
+        // window's own column count, embedding newlines that would inflate
+        // a `.chars().count()` measurement).
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+        for value in &window {
+            for (i, header) in headers.iter().enumerate() {
+                let cell_width = TableView::format_cell(value, header).0.chars().count();
+                if cell_width > widths[i] {
+                    widths[i] = cell_width;
+                }
+            }
+        }
+
+        if self.has_index {
+            let index_width = (self.starting_idx + window.len())
+                .to_string()
+                .chars()
+                .count();
+            headers.insert(0, "#".to_string());
+            widths.insert(0, index_width);
+            alignments.insert(0, Alignment::Right);
+        }
+
+        self.headers = headers;
+        self.alignments = alignments;
+
+        // Wide tables are always shown expanded, same as `TableView::from_list`/
+        // `render_view`, so records with many fields aren't silently packed
+        // into a grid that can't fit them.
+        let termwidth = std::cmp::max(textwrap::termwidth(), 20);
+        let max_num_of_columns = termwidth / 10;
+        let would_truncate = max_num_of_columns < self.headers.len();
+
+        if would_truncate || matches!(table_mode, TableMode::Expanded) {
+            self.mode = StreamMode::Expanded;
+            self.header_width = self
+                .headers
+                .iter()
+                .map(|h| h.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            for (offset, value) in window.iter().enumerate() {
+                self.write_expanded_row(self.starting_idx + offset, value, host)?;
+            }
+            self.next_idx = self.starting_idx + window.len();
+
+            return Ok(());
+        }
+
+        self.mode = StreamMode::Tabular;
+        self.widths = widths;
+        self.next_idx = self.starting_idx + window.len();
+
+        let out = host.out_terminal();
+
+        if !self.light_mode {
+            writeln!(out, "{}", self.border_line('━', '┯'))
+                .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+        }
+
+        self.write_line(&self.headers.clone(), host)?;
+
+        let title_cross = if self.light_mode { '─' } else { '┼' };
+        writeln!(
+            host.out_terminal(),
+            "{}",
+            self.border_line('─', title_cross)
+        )
+        .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+
+        for (offset, value) in window.iter().enumerate() {
+            self.write_row(self.starting_idx + offset, value, host)?;
+        }
+
+        Ok(())
+    }
+
+    // Formats one row against the widths and alignments locked in by
+    // `flush_window`, truncating or wrapping any cell that's grown past its
+    // established column width, per the `table_trim` config.
+    fn print_row(&mut self, value: &Value, host: &mut dyn Host) -> Result<(), ShellError> {
+        let idx = self.next_idx;
+        self.next_idx += 1;
+        self.write_row(idx, value, host)
+    }
+
+    fn write_row(&self, idx: usize, value: &Value, host: &mut dyn Host) -> Result<(), ShellError> {
+        let data_headers = if self.has_index {
+            &self.headers[1..]
+        } else {
+            &self.headers[..]
+        };
+
+        let mut cells: Vec<String> = data_headers
+            .iter()
+            .map(|desc| TableView::format_cell(value, desc).0)
+            .collect();
+
+        if self.has_index {
+            cells.insert(0, idx.to_string());
+        }
+
+        self.write_line(&cells, host)
+    }
+
+    fn write_line(&self, cells: &[String], host: &mut dyn Host) -> Result<(), ShellError> {
+        let separator = if self.light_mode { "   " } else { " │ " };
+
+        // Each cell wraps or truncates to its locked-in width first, which
+        // may split a wrapped cell across more than one physical line; the
+        // row then prints as that many lines, with shorter cells padded
+        // with blank space so every column's divider still lines up.
+        let wrapped: Vec<Vec<String>> = cells
+            .iter()
+            .zip(self.widths.iter())
+            .map(|(cell, &width)| match self.trim_mode {
+                TableTrim::Wrap => fill(cell, width).lines().map(str::to_string).collect(),
+                TableTrim::Truncate => vec![TableView::truncate_with_ellipsis(cell, width)],
+            })
+            .collect();
+
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        for line_idx in 0..height {
+            let rendered: Vec<String> = wrapped
+                .iter()
+                .zip(self.widths.iter())
+                .zip(self.alignments.iter())
+                .map(|((lines, &width), alignment)| {
+                    let line = lines.get(line_idx).map(String::as_str).unwrap_or("");
+                    match alignment {
+                        Alignment::Left => format!("{:<width$}", line, width = width),
+                        Alignment::Center => format!("{:^width$}", line, width = width),
+                        Alignment::Right => format!("{:>width$}", line, width = width),
+                    }
+                })
+                .collect();
+
+            writeln!(host.out_terminal(), " {} ", rendered.join(separator))
+                .map_err(|e| ShellError::untagged_runtime_error(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    // Builds a horizontal rule matching the locked-in column widths, e.g.
+    // `━━━┯━━━━━┯━━`, with `fill` repeated across each column (plus its
+    // 1-space padding on each side) and `cross` at the column boundaries.
+    fn border_line(&self, fill: char, cross: char) -> String {
+        self.widths
+            .iter()
+            .map(|width| fill.to_string().repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join(&cross.to_string())
+    }
+
+    // Formats one record against the header list locked in by
+    // `flush_window`, in the same vertical key/value layout
+    // `TableView::render_expanded` uses for a batch.
+    fn print_expanded_row(&mut self, value: &Value, host: &mut dyn Host) -> Result<(), ShellError> {
+        let idx = self.next_idx;
+        self.next_idx += 1;
+        self.write_expanded_row(idx, value, host)
+    }
+
+    fn write_expanded_row(
+        &self,
+        idx: usize,
+        value: &Value,
+        host: &mut dyn Host,
+    ) -> Result<(), ShellError> {
+        let data_headers = if self.has_index {
+            &self.headers[1..]
+        } else {
+            &self.headers[..]
+        };
+
+        let mut entry: Vec<(String, String)> = data_headers
+            .iter()
+            .map(|desc| {
+                let (text, style) = TableView::format_cell(value, desc);
+                (text, style.to_owned())
+            })
+            .collect();
+
+        if self.has_index {
+            entry.insert(0, (idx.to_string(), String::new()));
+        }
+
+        TableView::render_expanded_record(&self.headers, &entry, idx, self.header_width, host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alignment, GridDirection, TableView};
+    use nu_protocol::UntaggedValue;
+
+    #[test]
+    fn truncate_with_ellipsis_is_a_no_op_when_text_already_fits() {
+        assert_eq!(TableView::truncate_with_ellipsis("hello", 10), "hello");
+        assert_eq!(TableView::truncate_with_ellipsis("hello", 5), "hello");
+    }
+
+    #[test]
+    fn truncate_with_ellipsis_backs_up_to_a_word_boundary() {
+        assert_eq!(
+            TableView::truncate_with_ellipsis("hello world", 9),
+            "hello…"
+        );
+    }
+
+    #[test]
+    fn truncate_with_ellipsis_cuts_a_single_long_word_flat() {
+        // No space to back up to, so it just cuts at `max_width - 1`.
+        assert_eq!(
+            TableView::truncate_with_ellipsis("supercalifragilistic", 6),
+            "super…"
+        );
+    }
+
+    #[test]
+    fn truncate_with_ellipsis_handles_the_zero_and_one_width_edges() {
+        // `max_width == 0` can't even fit an ellipsis, so it's treated like
+        // "already fits" rather than underflowing `max_width - 1`.
+        assert_eq!(TableView::truncate_with_ellipsis("hello", 0), "hello");
+        // `max_width == 1` keeps zero characters and is just the ellipsis.
+        assert_eq!(TableView::truncate_with_ellipsis("hello", 1), "…");
+    }
+
+    #[test]
+    fn column_alignments_votes_right_for_an_all_numeric_column() {
+        let values = vec![
+            UntaggedValue::int(1).into_untagged_value(),
+            UntaggedValue::int(2).into_untagged_value(),
+        ];
+        let alignments = TableView::column_alignments(&values, &["<value>".to_string()]);
+
+        assert_eq!(alignments, vec![Alignment::Right]);
+    }
+
+    #[test]
+    fn column_alignments_votes_center_for_an_all_boolean_column() {
+        let values = vec![
+            UntaggedValue::boolean(true).into_untagged_value(),
+            UntaggedValue::boolean(false).into_untagged_value(),
+        ];
+        let alignments = TableView::column_alignments(&values, &["<value>".to_string()]);
+
+        assert_eq!(alignments, vec![Alignment::Center]);
+    }
+
+    #[test]
+    fn column_alignments_votes_left_for_a_mixed_column() {
+        // A column only gets right/center alignment on a unanimous vote;
+        // any dissent (here, one int and one string) defaults it to left.
+        let values = vec![
+            UntaggedValue::int(1).into_untagged_value(),
+            UntaggedValue::string("not a number").into_untagged_value(),
+        ];
+        let alignments = TableView::column_alignments(&values, &["<value>".to_string()]);
+
+        assert_eq!(alignments, vec![Alignment::Left]);
+    }
+
+    #[test]
+    fn proportional_widths_shrinks_toward_floor_when_over_budget() {
+        // Desired widths (10, 10, 10) don't fit in 18 columns of content
+        // width; since they're all equally over budget, they should shrink
+        // by the same proportion rather than one starving the others.
+        let widths = TableView::proportional_widths(&[10, 10, 10], &[3, 3, 3], 18);
+
+        assert_eq!(widths.iter().sum::<usize>(), 18);
+        assert!(widths.iter().all(|&w| w >= 3));
+    }
+
+    #[test]
+    fn proportional_widths_distributes_slack_when_under_budget() {
+        // There's more room than anything needs: the extra space should be
+        // handed out proportionally rather than left unused.
+        let widths = TableView::proportional_widths(&[4, 8], &[3, 3], 18);
+
+        assert_eq!(widths.iter().sum::<usize>(), 18);
+        assert!(widths[1] > widths[0]);
+    }
+
+    #[test]
+    fn proportional_widths_excludes_floored_columns_from_further_shrinking() {
+        // The first column's desired width is already at its floor, so all
+        // of the shrinking has to come out of the second column instead of
+        // squeezing the first column below its floor.
+        let widths = TableView::proportional_widths(&[3, 17], &[3, 3], 10);
+
+        assert_eq!(widths[0], 3);
+        assert_eq!(widths.iter().sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn fit_grid_packs_many_narrow_values_into_a_few_lines() {
+        // 100 single-character values in an 80-column terminal should pack
+        // into a multi-column grid, not fall back to one value per line.
+        let widths = vec![1; 100];
+        let (num_lines, num_columns, _) = TableView::fit_grid(&widths, 80, 2, GridDirection::Down);
+
+        assert!(num_lines < 100);
+        assert!(num_columns > 1);
+        assert!(num_lines * num_columns >= 100);
+    }
+
+    #[test]
+    fn fit_grid_continues_past_a_too_wide_early_candidate() {
+        // num_lines=1 (all eight values on one line) can't possibly fit these widths, but
+        // the search must keep trying taller/narrower arrangements instead
+        // of aborting outright.
+        let widths = vec![5, 5, 5, 5, 5, 5, 5, 5];
+        let (num_lines, num_columns, col_widths) =
+            TableView::fit_grid(&widths, 20, 2, GridDirection::Down);
+
+        assert!(num_lines > 1);
+        let total: usize = col_widths.iter().sum::<usize>() + 2 * num_columns.saturating_sub(1);
+        assert!(total <= 20);
+    }
+
+    #[test]
+    fn fit_grid_falls_back_to_one_per_line_when_nothing_fits() {
+        let widths = vec![100, 100, 100];
+        let (num_lines, num_columns, _) = TableView::fit_grid(&widths, 10, 2, GridDirection::Down);
+
+        assert_eq!(num_lines, 3);
+        assert_eq!(num_columns, 1);
+    }
+}